@@ -8,84 +8,250 @@
 //! # Lazy Singleton initialization
 //!
 
+use crate::pin_init::PinInit;
+use crate::Relax;
 use core::cell::UnsafeCell;
+use core::convert::Infallible;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicBool, Ordering};
 use ruspiro_lock::Spinlock;
 
-/// A wrapper that enables lazy initialization of the value stored within the `Singleton`
-pub struct LazyValue<T: 'static + Sized> {
-  /// the actual value that shall be provided as a singleton
-  inner: UnsafeCell<Option<T>>,
-  /// the closure used to initialize the singleton
+/// A wrapper that enables lazy initialization of the value stored within the `Singleton`. The value is kept in place
+/// inside a `MaybeUninit` cell so that it can also be constructed directly at its final address for address sensitive
+/// (pinned) types. `E` is the error type of a fallible in-place initializer and defaults to [Infallible].
+pub struct LazyValue<T: 'static + Sized, E: 'static = Infallible> {
+  /// the actual value that shall be provided as a singleton, kept in place and only valid while `initialized` is set
+  inner: UnsafeCell<MaybeUninit<T>>,
+  /// whether `inner` currently holds a valid value
+  initialized: AtomicBool,
+  /// the closure used to initialize the singleton by value
   init: Option<&'static dyn Fn() -> T>,
+  /// the fallible closure used to initialize the singleton by value, storing the value on `Ok` and leaving the cell
+  /// uninitialized on `Err` so a later access can retry
+  try_init: Option<&'static dyn Fn() -> Result<T, E>>,
+  /// the in-place initializer used to construct the singleton directly at its final address
+  pin_init: Option<&'static dyn PinInit<T, E>>,
   /// A lock that secures the lazy update of the inner value in case it happens across cores
   lock: Spinlock,
 }
 
-impl<T: 'static + Sized> LazyValue<T> {
-  /// create a new [LazySingleton] where the value is already available
+impl<T: 'static + Sized, E: 'static> LazyValue<T, E> {
+  /// create a new [LazyValue] where the value is already available
   pub const fn with_value(value: T) -> Self {
     Self {
-      inner: UnsafeCell::new(Some(value)),
+      inner: UnsafeCell::new(MaybeUninit::new(value)),
+      initialized: AtomicBool::new(true),
       init: None,
+      try_init: None,
+      pin_init: None,
       lock: Spinlock::new(),
     }
   }
 
-  /// create a new [LazySingleton] where the actual value will be lazily created at first access
+  /// create a new [LazyValue] where the actual value will be lazily created at first access
   pub const fn with_init<F>(init: &'static F) -> Self
   where
     F: Fn() -> T,
   {
     Self {
-      inner: UnsafeCell::new(None),
+      inner: UnsafeCell::new(MaybeUninit::uninit()),
+      initialized: AtomicBool::new(false),
       init: Some(init),
+      try_init: None,
+      pin_init: None,
       lock: Spinlock::new(),
     }
   }
 
+  /// create a new [LazyValue] whose value is created at first access by the given fallible closure. On `Ok` the value
+  /// is stored, on `Err` the cell is left uninitialized so a later access can retry (see [try_get]).
+  pub const fn with_try_init<F>(init: &'static F) -> Self
+  where
+    F: Fn() -> Result<T, E>,
+  {
+    Self {
+      inner: UnsafeCell::new(MaybeUninit::uninit()),
+      initialized: AtomicBool::new(false),
+      init: None,
+      try_init: Some(init),
+      pin_init: None,
+      lock: Spinlock::new(),
+    }
+  }
+
+  /// create a new [LazyValue] whose value is constructed in place at first access by the given [PinInit]
+  pub const fn with_pin_init(init: &'static dyn PinInit<T, E>) -> Self {
+    Self {
+      inner: UnsafeCell::new(MaybeUninit::uninit()),
+      initialized: AtomicBool::new(false),
+      init: None,
+      try_init: None,
+      pin_init: Some(init),
+      lock: Spinlock::new(),
+    }
+  }
+
+  /// raw pointer to the in-place storage of the contained value
+  fn as_ptr(&self) -> *mut T {
+    // casting the `MaybeUninit<T>` storage to a `*mut T` - only dereferenced once `initialized` is set
+    unsafe { (*self.inner.get()).as_mut_ptr() }
+  }
+
   fn set(&self, value: T) -> Result<(), T> {
-    let inner = unsafe { &*self.inner.get() };
-    if inner.is_some() {
+    if self.initialized.load(Ordering::Acquire) {
       return Err(value);
     }
-    // update the actual value of LazyValue. This is safe as this is the
-    // only place this is updated and we checked the value is actually None
-    // before
-    let inner = unsafe { &mut *self.inner.get() };
-    *inner = Some(value);
+    // update the actual value of LazyValue. This is safe as the value is only ever written under the lock (here or by
+    // the in-place initializer) and we checked the value is actually not initialized before
+    unsafe { self.as_ptr().write(value) };
+    self.initialized.store(true, Ordering::Release);
 
     Ok(())
   }
 
-  fn init(&self) {
-    // locking the spinlock to ensure the initialization really happens
-    // exclusively
-    self.lock.aquire();
+  fn init<R: Relax>(&self) {
+    // locking the spinlock to ensure the initialization really happens exclusively. Instead of the plain blocking
+    // `aquire` we spin using the configured relax strategy so a contended core may park via `wfe` on the Pi.
+    while !self.lock.try_lock() {
+      R::relax();
+    }
     // if we could aquire the lock there is a probability that the initialization was kind of a longer running
     // task and thus has already happened while waiting for the lock. So check if the value is still not initialized
-    if unsafe { &*self.inner.get() }.is_none() {
-      let init = self.init.unwrap();
-      let value = init();
-      assert!(self.set(value).is_ok(), "LazyValue initialized twice");
+    if !self.initialized.load(Ordering::Acquire) {
+      if let Some(pin_init) = self.pin_init {
+        // construct the value directly into its final storage. This infallible accessor can only surface a failure by
+        // panicking; use the fallible [try_get]/[try_get_mut] path to propagate the error instead. For the default
+        // `Infallible` error type this branch never fails.
+        let ok = unsafe { pin_init.pinned_init(self.as_ptr()) }.is_ok();
+        assert!(ok, "LazyValue pinned initializer failed");
+        self.initialized.store(true, Ordering::Release);
+      } else if let Some(try_init) = self.try_init {
+        // an infallible accessor on a fallibly initialized cell can only report a failure by panicking; use the
+        // fallible [try_get]/[try_get_mut] path to observe the error instead
+        match try_init() {
+          Ok(value) => assert!(self.set(value).is_ok(), "LazyValue initialized twice"),
+          Err(_) => panic!("LazyValue fallible initializer failed"),
+        }
+      } else {
+        let init = self.init.unwrap();
+        let value = init();
+        assert!(self.set(value).is_ok(), "LazyValue initialized twice");
+      }
     }
     self.lock.release();
+    // wake any core parked while waiting for the initialization to complete
+    R::notify();
   }
 
-  pub fn get(&self) -> &T {
-    if let Some(inner) = unsafe { &*self.inner.get() }.as_ref() {
-      inner
-    } else {
-      self.init();
-      unsafe { &*self.inner.get() }.as_ref().unwrap()
+  /// Return a reference to the contained value, lazily and fallibly initializing it with the given closure if it is
+  /// not yet present. Mirrors std's `OnceCell::get_or_try_init`: on `Ok` the produced value is stored and returned,
+  /// on `Err` the cell is left uninitialized - so a later access can retry - and the error is propagated to the
+  /// caller. The spinlock guarding the set only commits on success, preserving the "initialized exactly once"
+  /// invariant.
+  pub fn get_or_try_init<R, F, Ei>(&self, init: F) -> Result<&T, Ei>
+  where
+    R: Relax,
+    F: FnOnce() -> Result<T, Ei>,
+  {
+    // fast path - the value is already there
+    if self.initialized.load(Ordering::Acquire) {
+      return Ok(unsafe { &*self.as_ptr() });
+    }
+    // spin for the init lock relaxing the core on contention, see [init]
+    while !self.lock.try_lock() {
+      R::relax();
     }
+    // re-check after aquiring the lock as another core may have initialized the value while we were waiting
+    if !self.initialized.load(Ordering::Acquire) {
+      match init() {
+        Ok(value) => {
+          assert!(self.set(value).is_ok(), "LazyValue initialized twice");
+        }
+        Err(e) => {
+          // initialization failed - leave the cell uninitialized so the next access can retry, propagate the error
+          self.lock.release();
+          R::notify();
+          return Err(e);
+        }
+      }
+    }
+    self.lock.release();
+    R::notify();
+    Ok(unsafe { &*self.as_ptr() })
   }
 
-  pub fn get_mut(&self) -> &mut T {
-    if let Some(inner) = unsafe { &mut *self.inner.get() }.as_mut() {
-      inner
+  /// Run the stored fallible initializer (from [with_try_init] or [with_pin_init]) under the spinlock if the value is
+  /// not yet present. On `Ok` the value is committed and left initialized, on `Err` the cell stays uninitialized so a
+  /// later access can retry and the error is propagated to the caller - mirroring [get_or_try_init].
+  fn try_init<R: Relax>(&self) -> Result<(), E> {
+    // spin for the init lock relaxing the core on contention, see [init]
+    while !self.lock.try_lock() {
+      R::relax();
+    }
+    // re-check after aquiring the lock as another core may have initialized the value while we were waiting
+    let result = if !self.initialized.load(Ordering::Acquire) {
+      if let Some(pin_init) = self.pin_init {
+        // construct the value in place, committing only on success
+        unsafe { pin_init.pinned_init(self.as_ptr()) }.map(|()| self.initialized.store(true, Ordering::Release))
+      } else {
+        let init = self.try_init.expect("LazyValue has no fallible initializer");
+        match init() {
+          Ok(value) => {
+            assert!(self.set(value).is_ok(), "LazyValue initialized twice");
+            Ok(())
+          }
+          // leave the cell uninitialized so the next access can retry, propagate the error
+          Err(e) => Err(e),
+        }
+      }
     } else {
-      self.init();
-      unsafe { &mut *self.inner.get() }.as_mut().unwrap()
+      Ok(())
+    };
+    self.lock.release();
+    // wake any core parked while waiting for the initialization to complete
+    R::notify();
+
+    result
+  }
+
+  /// Return a reference to the contained value, lazily initializing it via the stored fallible initializer if needed.
+  /// Propagates the initializer error on failure, see [try_init].
+  pub fn try_get<R: Relax>(&self) -> Result<&T, E> {
+    if !self.initialized.load(Ordering::Acquire) {
+      self.try_init::<R>()?;
+    }
+    Ok(unsafe { &*self.as_ptr() })
+  }
+
+  /// Return a mutable reference to the contained value, lazily initializing it via the stored fallible initializer if
+  /// needed. Propagates the initializer error on failure, see [try_init].
+  pub fn try_get_mut<R: Relax>(&self) -> Result<&mut T, E> {
+    if !self.initialized.load(Ordering::Acquire) {
+      self.try_init::<R>()?;
+    }
+    Ok(unsafe { &mut *self.as_ptr() })
+  }
+
+  pub fn get<R: Relax>(&self) -> &T {
+    if !self.initialized.load(Ordering::Acquire) {
+      self.init::<R>();
+    }
+    unsafe { &*self.as_ptr() }
+  }
+
+  pub fn get_mut<R: Relax>(&self) -> &mut T {
+    if !self.initialized.load(Ordering::Acquire) {
+      self.init::<R>();
+    }
+    unsafe { &mut *self.as_ptr() }
+  }
+}
+
+impl<T: 'static + Sized, E: 'static> Drop for LazyValue<T, E> {
+  fn drop(&mut self) {
+    // drop the contained value in place if it was ever initialized
+    if *self.initialized.get_mut() {
+      unsafe { core::ptr::drop_in_place(self.as_ptr()) };
     }
   }
 }
@@ -69,22 +69,42 @@
 //! ```
 
 mod lazy;
+mod locked_by;
+mod pin_init;
+mod relax;
 
+use core::convert::Infallible;
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicUsize, Ordering};
 use lazy::LazyValue;
+pub use locked_by::LockedBy;
+pub use pin_init::PinInit;
+pub use relax::{Relax, Spin, WaitForEvent};
 use ruspiro_lock::RWLock;
 
-/// The Singleton wrapper stores any type
-pub struct Singleton<T: 'static> {
+/// The Singleton wrapper stores any type. The second type parameter selects the [Relax] strategy used while spinning
+/// on the contended inner write lock and defaults to a plain [Spin]. The third type parameter is the error type of a
+/// fallible in-place initializer (see [pin_init](Singleton::pin_init)) and defaults to [Infallible].
+pub struct Singleton<T: 'static, S: Relax = Spin, E: 'static = Infallible> {
     /// the inner value wrapping the contained data for safe read/write access
-    inner: RWLock<LazyValue<T>>,
+    inner: RWLock<LazyValue<T, E>>,
+    /// when set the write lock is aquired in FIFO ticket order to guarantee bounded wait across cores
+    fair: bool,
+    /// the next ticket handed out to a core claiming the write lock in fair mode
+    next_ticket: AtomicUsize,
+    /// the ticket that is currently allowed to aquire the write lock in fair mode
+    now_serving: AtomicUsize,
+    /// zero sized marker pinning the relax strategy used by this singleton
+    _relax: PhantomData<S>,
 }
 
 // The Singleton need to implement Send & Sync to ensure cross core compile check mechanics
 // this is safe as the inner RWLock ensures cross core safety
-unsafe impl<T> Sync for Singleton<T> {}
-unsafe impl<T> Send for Singleton<T> {}
+unsafe impl<T, S: Relax, E> Sync for Singleton<T, S, E> {}
+unsafe impl<T, S: Relax, E> Send for Singleton<T, S, E> {}
 
-impl<T: 'static> Singleton<T> {
+impl<T: 'static, S: Relax, E: 'static> Singleton<T, S, E> {
     /// Create a new [Singleton] instance to be used in a static variable. Only ``const fn`` constructors are allowed
     /// here.
     /// # Example
@@ -96,6 +116,30 @@ impl<T: 'static> Singleton<T> {
     pub const fn new(value: T) -> Self {
         Singleton {
             inner: RWLock::new(LazyValue::with_value(value)),
+            fair: false,
+            next_ticket: AtomicUsize::new(0),
+            now_serving: AtomicUsize::new(0),
+            _relax: PhantomData,
+        }
+    }
+
+    /// Create a new [Singleton] instance that aquires its write lock in fair FIFO ticket order. Use this instead of
+    /// [new](Singleton::new) when bounded wait / ordering across cores is required so a busy core cannot repeatedly
+    /// win reacquisition and starve another core waiting to access the singleton. Only the blocking write accessors
+    /// queue for a ticket; [try_with_mut](Singleton::try_with_mut) and the read accessors take the lock without one.
+    /// # Example
+    /// ```no_run
+    /// # use ruspiro_singleton::*;
+    /// static FOO: Singleton<u32> = Singleton::new_fair(20);
+    /// # fn main() {}
+    /// ```
+    pub const fn new_fair(value: T) -> Self {
+        Singleton {
+            inner: RWLock::new(LazyValue::with_value(value)),
+            fair: true,
+            next_ticket: AtomicUsize::new(0),
+            now_serving: AtomicUsize::new(0),
+            _relax: PhantomData,
         }
     }
 
@@ -113,6 +157,99 @@ impl<T: 'static> Singleton<T> {
     {
         Self {
             inner: RWLock::new(LazyValue::with_init(init)),
+            fair: false,
+            next_ticket: AtomicUsize::new(0),
+            now_serving: AtomicUsize::new(0),
+            _relax: PhantomData,
+        }
+    }
+
+    /// Create a new lazily initialized [Singleton] that aquires its write lock in fair FIFO ticket order. See
+    /// [new_fair](Singleton::new_fair) and [lazy](Singleton::lazy).
+    /// # Example
+    /// ```no_run
+    /// # use ruspiro_singleton::*;
+    /// static FOO: Singleton<String> = Singleton::lazy_fair(&|| String::from("foo"));
+    /// # fn main() {}
+    /// ```
+    pub const fn lazy_fair<F>(init: &'static F) -> Self
+    where
+        F: Fn() -> T,
+    {
+        Self {
+            inner: RWLock::new(LazyValue::with_init(init)),
+            fair: true,
+            next_ticket: AtomicUsize::new(0),
+            now_serving: AtomicUsize::new(0),
+            _relax: PhantomData,
+        }
+    }
+
+    /// Create a new [Singleton] whose value is produced by a fallible closure evaluated at first access. Unlike
+    /// [lazy](Singleton::lazy) the stored initializer may fail: it is run once on the first
+    /// [with_mut_lazy](Singleton::with_mut_lazy) / [with_ref_lazy](Singleton::with_ref_lazy) access, on `Ok` the value
+    /// is stored and reused by all later accesses, on `Err` the singleton is left uninitialized so the next access can
+    /// retry and the error `E` is returned to the caller instead of panicking the core.
+    /// # Example
+    /// ```no_run
+    /// # use ruspiro_singleton::*;
+    /// static FOO: Singleton<u32, Spin, &'static str> =
+    ///     Singleton::try_lazy(&|| Err("hardware not ready"));
+    /// # fn main() {}
+    /// ```
+    pub const fn try_lazy<F>(init: &'static F) -> Self
+    where
+        F: Fn() -> Result<T, E>,
+    {
+        Self {
+            inner: RWLock::new(LazyValue::with_try_init(init)),
+            fair: false,
+            next_ticket: AtomicUsize::new(0),
+            now_serving: AtomicUsize::new(0),
+            _relax: PhantomData,
+        }
+    }
+
+    /// Create a new [Singleton] whose value is constructed in place at its final static address by the given
+    /// [PinInit] initializer at first access. This is required for address sensitive peripheral types (self
+    /// referential structs, DMA descriptors, register mirrors) that must never be moved: the value is written
+    /// directly into the singleton's storage and, as the singleton lives in a `'static`, is guaranteed not to move
+    /// afterwards. Access the pinned contents via [with_pin_ref](Singleton::with_pin_ref) and
+    /// [with_pin_mut](Singleton::with_pin_mut) for an infallible initializer, or via
+    /// [try_with_pin_ref](Singleton::try_with_pin_ref) / [try_with_pin_mut](Singleton::try_with_pin_mut) when the
+    /// initializer may fail (error type `E`) so the failure is returned instead of panicking the core.
+    pub const fn pin_init<I>(init: &'static I) -> Self
+    where
+        I: PinInit<T, E>,
+    {
+        Singleton {
+            inner: RWLock::new(LazyValue::with_pin_init(init)),
+            fair: false,
+            next_ticket: AtomicUsize::new(0),
+            now_serving: AtomicUsize::new(0),
+            _relax: PhantomData,
+        }
+    }
+
+    /// In fair mode claim the next ticket and spin (relaxing the core) until it is our turn to aquire the write lock.
+    /// A no-op for the default unfair singleton.
+    fn take_ticket(&self) {
+        if self.fair {
+            let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+            while self.now_serving.load(Ordering::Acquire) != ticket {
+                S::relax();
+            }
+        }
+    }
+
+    /// In fair mode advance the queue so the next ticket holder may aquire the write lock. A no-op for the default
+    /// unfair singleton. Advancing the queue is a release that can satisfy a core parked in [take_ticket] waiting for
+    /// its ticket, so we wake parked cores via the relax strategy - otherwise a core parked with `wfe` would only be
+    /// woken by an unrelated writer's notify (or never), deadlocking the fair + [WaitForEvent] configuration.
+    fn advance_ticket(&self) {
+        if self.fair {
+            self.now_serving.fetch_add(1, Ordering::Release);
+            S::notify();
         }
     }
 
@@ -123,17 +260,188 @@ impl<T: 'static> Singleton<T> {
     where
         F: FnOnce(&mut T) -> R,
     {
-        let inner = self.inner.lock();
+        // in fair mode wait for our ticket before competing for the write lock
+        self.take_ticket();
+        // spin for the write lock using the configured relax strategy instead of the plain blocking `lock`, so on the
+        // Pi cores a contended aquisition parks the core via `wfe` instead of busy spinning
+        let inner = loop {
+            if let Some(guard) = self.inner.try_lock() {
+                break guard;
+            }
+            S::relax();
+        };
         // use write lock to mutably access the inner value of the singleton. As long
         // as the write lock exists no other write or read lock is possible
-        let r = f(inner.get_mut());
+        let r = f(inner.get_mut::<S>());
 
         // explicitly release the lock befor providing the result of the closure to the caller
         drop(inner);
+        // advance the fair queue and wake any core parked while waiting for this write lock
+        self.advance_ticket();
+        S::notify();
 
         r
     }
 
+    /// Try to take the stored singleton for a mutable operation without blocking. In contrast to [with_mut] this does
+    /// not spin until the write lock could be aquired but attempts the aquisition exactly once. If the lock is
+    /// currently held by another core the closure is not executed and `None` is returned immediately. This is usefull
+    /// to opportunistically access the singleton from an interrupt handler where unbounded spinning would risk a
+    /// deadlock.
+    ///
+    pub fn try_with_mut<F, R>(&self, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        let inner = self.inner.try_lock()?;
+        // we aquired the write lock without blocking - mutably access the inner value of the singleton
+        let r = f(inner.get_mut::<S>());
+
+        // explicitly release the lock befor providing the result of the closure to the caller
+        drop(inner);
+        // wake any core parked while waiting for this write lock
+        S::notify();
+
+        Some(r)
+    }
+
+    /// Try to access the stored singleton for a read-only operation without blocking. This attempts to aquire a read
+    /// lock exactly once and returns `None` immediately if a writer currently holds the lock instead of spinning. See
+    /// [try_with_mut] for the mutable counterpart.
+    ///
+    pub fn try_with_ref<F, R>(&self, f: F) -> Option<R>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        let inner = self.inner.try_read()?;
+        // we aquired a read lock without blocking - access the inner value of the singleton
+        let r = f(inner.get::<S>());
+
+        // explicitly release the lock befor providing the result of the closure to the caller
+        drop(inner);
+        // wake any core parked while waiting to aquire the write lock
+        S::notify();
+
+        Some(r)
+    }
+
+    /// Mutable access to a singleton whose value is produced by a fallible lazy initializer. If the singleton is not
+    /// yet initialized the `init` closure is run; on `Ok` the value is stored and `f` is executed with mutable access
+    /// to it, on `Err` the singleton is left uninitialized - so a later access can retry - and the error is returned
+    /// to the caller. If the singleton is already initialized `init` is not called. This allows a lazy constructor
+    /// that can fail (hardware not ready, allocation failed) to report the failure instead of panicking the core.
+    ///
+    pub fn with_mut_or_init<I, Ei, F, R>(&self, init: I, f: F) -> Result<R, Ei>
+    where
+        I: FnOnce() -> Result<T, Ei>,
+        F: FnOnce(&mut T) -> R,
+    {
+        // in fair mode wait for our ticket before competing for the write lock
+        self.take_ticket();
+        let inner = loop {
+            if let Some(guard) = self.inner.try_lock() {
+                break guard;
+            }
+            S::relax();
+        };
+        // fallibly initialize the value; only run the closure once the value is actually present
+        let result = match inner.get_or_try_init::<S, _, _>(init) {
+            Ok(_) => Ok(f(inner.get_mut::<S>())),
+            Err(e) => Err(e),
+        };
+
+        // explicitly release the lock befor providing the result of the closure to the caller
+        drop(inner);
+        // advance the fair queue and wake any core parked while waiting for this write lock
+        self.advance_ticket();
+        S::notify();
+
+        result
+    }
+
+    /// Read-only access to a singleton whose value is produced by a fallible lazy initializer. Behaves like
+    /// [with_mut_or_init](Singleton::with_mut_or_init) but runs `f` with a shared reference to the value.
+    ///
+    pub fn with_ref_or_init<I, Ei, F, R>(&self, init: I, f: F) -> Result<R, Ei>
+    where
+        I: FnOnce() -> Result<T, Ei>,
+        F: FnOnce(&T) -> R,
+    {
+        // spin for a read lock relaxing the core on contention, see [with_ref]
+        let inner = loop {
+            if let Some(guard) = self.inner.try_read() {
+                break guard;
+            }
+            S::relax();
+        };
+        // fallibly initialize the value and run the closure with a shared reference on success
+        let result = inner.get_or_try_init::<S, _, _>(init).map(f);
+
+        // explicitly release the lock befor providing the result of the closure to the caller
+        drop(inner);
+        // releasing a read lock may satisfy a core waiting for the write lock, so wake parked cores
+        S::notify();
+
+        result
+    }
+
+    /// Mutable access to a singleton created with [try_lazy](Singleton::try_lazy). Runs the stored fallible
+    /// initializer on the first access; on `Ok` the value is stored and `f` is executed with mutable access to it, on
+    /// `Err` the singleton is left uninitialized - so a later access can retry - and the error is returned. If the
+    /// singleton is already initialized the stored initializer is not run again.
+    ///
+    pub fn with_mut_lazy<F, R>(&self, f: F) -> Result<R, E>
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        // in fair mode wait for our ticket before competing for the write lock
+        self.take_ticket();
+        let inner = loop {
+            if let Some(guard) = self.inner.try_lock() {
+                break guard;
+            }
+            S::relax();
+        };
+        // run the stored fallible initializer and only execute the closure once the value is actually present
+        let result = match inner.try_get_mut::<S>() {
+            Ok(value) => Ok(f(value)),
+            Err(e) => Err(e),
+        };
+
+        // explicitly release the lock befor providing the result of the closure to the caller
+        drop(inner);
+        // advance the fair queue and wake any core parked while waiting for this write lock
+        self.advance_ticket();
+        S::notify();
+
+        result
+    }
+
+    /// Read-only access to a singleton created with [try_lazy](Singleton::try_lazy). Behaves like
+    /// [with_mut_lazy](Singleton::with_mut_lazy) but runs `f` with a shared reference to the value.
+    ///
+    pub fn with_ref_lazy<F, R>(&self, f: F) -> Result<R, E>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        // spin for a read lock relaxing the core on contention, see [with_ref]
+        let inner = loop {
+            if let Some(guard) = self.inner.try_read() {
+                break guard;
+            }
+            S::relax();
+        };
+        // run the stored fallible initializer and run the closure with a shared reference on success
+        let result = inner.try_get::<S>().map(f);
+
+        // explicitly release the lock befor providing the result of the closure to the caller
+        drop(inner);
+        // releasing a read lock may satisfy a core waiting for the write lock, so wake parked cores
+        S::notify();
+
+        result
+    }
+
     /// Immutable access to a singleton for a specific operation.
     /// This access does not enforce any lock nor guarantees safe atomic access to the instance. However, it is usefull
     /// in read-only access scenarios like inside interrupt handlers.
@@ -142,14 +450,265 @@ impl<T: 'static> Singleton<T> {
     where
         F: FnOnce(&T) -> R,
     {
-        let inner = self.inner.read();
+        // spin for a read lock using the configured relax strategy instead of the plain blocking `read`, so on the Pi
+        // cores a reader contending with a writer parks via `wfe` instead of busy spinning inside the RWLock
+        let inner = loop {
+            if let Some(guard) = self.inner.try_read() {
+                break guard;
+            }
+            S::relax();
+        };
         // multiple read locks are possible when accessing the inner data of the singleton
         // all read locks are required to be release before the next write lock could happen
-        let r = f(inner.get());
+        let r = f(inner.get::<S>());
 
         // explicitly release the lock befor providing the result of the closure to the caller
         drop(inner);
+        // releasing a read lock may satisfy a core waiting for the write lock, so wake parked cores
+        S::notify();
 
         r
     }
+
+    /// Block the calling core until the data stored inside the singleton satisfies the given predicate and then run
+    /// the closure with mutable access to it, returning its result. This is a condition variable style handshake: the
+    /// predicate is evaluated under the write lock, and while it is not satisfied the lock is released and the core is
+    /// parked using the relax strategy until another core calls [notify](Singleton::notify) /
+    /// [notify_all](Singleton::notify_all) after updating the contents. A spurious wake simply re-aquires the lock and
+    /// re-checks the predicate. This avoids the caller re-entering [with_mut] in a hot spin loop, e.g. to wait until a
+    /// peripheral FIFO has data.
+    ///
+    /// # HINT
+    /// Real core parking only happens when the singleton selects the [WaitForEvent] relax strategy. With the default
+    /// [Spin] strategy (and therefore on every host / non-AArch64 build) the "park" degrades to a busy re-acquire and
+    /// re-check loop, so the waiter keeps the core busy until the predicate is satisfied.
+    ///
+    pub fn wait_until<P, F, R>(&self, pred: P, f: F) -> R
+    where
+        P: Fn(&T) -> bool,
+        F: FnOnce(&mut T) -> R,
+    {
+        loop {
+            // in fair mode wait for our ticket before competing for the write lock
+            self.take_ticket();
+            // aquire the write lock relaxing the core on contention, see [with_mut]
+            let inner = loop {
+                if let Some(guard) = self.inner.try_lock() {
+                    break guard;
+                }
+                S::relax();
+            };
+            if pred(inner.get::<S>()) {
+                // the predicate holds - run the closure while still holding the write lock
+                let r = f(inner.get_mut::<S>());
+
+                drop(inner);
+                // advance the fair queue and wake parked cores, our update may satisfy another waiter
+                self.advance_ticket();
+                S::notify();
+
+                return r;
+            }
+            // predicate not satisfied yet - release the lock so a writer may make progress, hand on our ticket, wake
+            // any core parked while waiting for the write lock and park the core until an event wakes us to re-check
+            drop(inner);
+            self.advance_ticket();
+            S::notify();
+            S::relax();
+        }
+    }
+
+    /// Wake a core that parked itself inside [wait_until](Singleton::wait_until) so it re-checks its predicate. Call
+    /// this after mutating the singleton in a way that could satisfy a waiter.
+    ///
+    /// # HINT
+    /// There is no single-waiter wake on the `sev` event mechanism: a `sev` wakes every parked core. `notify` is
+    /// therefore identical to [notify_all](Singleton::notify_all) and both are kept only to document intent at the
+    /// call site.
+    ///
+    pub fn notify(&self) {
+        S::notify();
+    }
+
+    /// Wake all cores parked inside [wait_until](Singleton::wait_until) so they re-check their predicate. Call this
+    /// after mutating the singleton in a way that could satisfy several waiters. Identical to [notify](Singleton::notify)
+    /// as `sev` always wakes every parked core, see there.
+    ///
+    pub fn notify_all(&self) {
+        S::notify();
+    }
+
+    /// Take the stored singleton for a mutable operation, handing the closure a `Pin<&mut T>` so an address sensitive
+    /// value can never be moved out. Use together with [pin_init](Singleton::pin_init) for peripheral types that
+    /// require a stable address.
+    ///
+    pub fn with_pin_mut<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(Pin<&mut T>) -> R,
+    {
+        // in fair mode wait for our ticket before competing for the write lock
+        self.take_ticket();
+        let inner = loop {
+            if let Some(guard) = self.inner.try_lock() {
+                break guard;
+            }
+            S::relax();
+        };
+        // SAFETY: the singleton lives in a `'static` and its contents are constructed in place and never moved out,
+        // so handing out a pinned reference upholds the pinning invariant
+        let pinned = unsafe { Pin::new_unchecked(inner.get_mut::<S>()) };
+        let r = f(pinned);
+
+        // explicitly release the lock befor providing the result of the closure to the caller
+        drop(inner);
+        // advance the fair queue and wake any core parked while waiting for this write lock
+        self.advance_ticket();
+        S::notify();
+
+        r
+    }
+
+    /// Read-only access to the stored singleton, handing the closure a `Pin<&T>`. See
+    /// [with_pin_mut](Singleton::with_pin_mut).
+    ///
+    pub fn with_pin_ref<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(Pin<&T>) -> R,
+    {
+        // spin for a read lock relaxing the core on contention, see [with_ref]
+        let inner = loop {
+            if let Some(guard) = self.inner.try_read() {
+                break guard;
+            }
+            S::relax();
+        };
+        // SAFETY: see [with_pin_mut] - the contents never move out of the `'static` singleton
+        let pinned = unsafe { Pin::new_unchecked(inner.get::<S>()) };
+        let r = f(pinned);
+
+        // explicitly release the lock befor providing the result of the closure to the caller
+        drop(inner);
+        // releasing a read lock may satisfy a core waiting for the write lock, so wake parked cores
+        S::notify();
+
+        r
+    }
+
+    /// Mutable pinned access to a singleton created with a fallible [pin_init](Singleton::pin_init) initializer. Runs
+    /// the in-place initializer on the first access; on `Ok` the value is constructed at its final address and the
+    /// closure is handed a `Pin<&mut T>`, on `Err` the singleton is left uninitialized - so a later access can retry -
+    /// and the error is returned instead of panicking the core (which is what the infallible
+    /// [with_pin_mut](Singleton::with_pin_mut) does on failure).
+    ///
+    pub fn try_with_pin_mut<F, R>(&self, f: F) -> Result<R, E>
+    where
+        F: FnOnce(Pin<&mut T>) -> R,
+    {
+        // in fair mode wait for our ticket before competing for the write lock
+        self.take_ticket();
+        let inner = loop {
+            if let Some(guard) = self.inner.try_lock() {
+                break guard;
+            }
+            S::relax();
+        };
+        // construct the value in place, only handing out the pinned reference once it is actually present
+        let result = match inner.try_get_mut::<S>() {
+            // SAFETY: see [with_pin_mut] - the contents never move out of the `'static` singleton
+            Ok(value) => Ok(f(unsafe { Pin::new_unchecked(value) })),
+            Err(e) => Err(e),
+        };
+
+        // explicitly release the lock befor providing the result of the closure to the caller
+        drop(inner);
+        // advance the fair queue and wake any core parked while waiting for this write lock
+        self.advance_ticket();
+        S::notify();
+
+        result
+    }
+
+    /// Read-only pinned access to a singleton created with a fallible [pin_init](Singleton::pin_init) initializer.
+    /// Behaves like [try_with_pin_mut](Singleton::try_with_pin_mut) but runs `f` with a `Pin<&T>`.
+    ///
+    pub fn try_with_pin_ref<F, R>(&self, f: F) -> Result<R, E>
+    where
+        F: FnOnce(Pin<&T>) -> R,
+    {
+        // spin for a read lock relaxing the core on contention, see [with_ref]
+        let inner = loop {
+            if let Some(guard) = self.inner.try_read() {
+                break guard;
+            }
+            S::relax();
+        };
+        // SAFETY: see [with_pin_mut] - the contents never move out of the `'static` singleton
+        let result = match inner.try_get::<S>() {
+            Ok(value) => Ok(f(unsafe { Pin::new_unchecked(value) })),
+            Err(e) => Err(e),
+        };
+
+        // explicitly release the lock befor providing the result of the closure to the caller
+        drop(inner);
+        // releasing a read lock may satisfy a core waiting for the write lock, so wake parked cores
+        S::notify();
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_with_mut_returns_none_while_write_lock_held() {
+        static FOO: Singleton<u32> = Singleton::new(0);
+        // while the write lock is held inside `with_mut` a non-blocking attempt must not succeed
+        let inner = FOO.with_mut(|_outer| FOO.try_with_mut(|_inner| ()));
+        assert!(inner.is_none());
+    }
+
+    #[test]
+    fn try_lazy_leaves_cell_uninitialized_and_retries_after_err() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+        static FOO: Singleton<u32, Spin, &'static str> = Singleton::try_lazy(&|| {
+            // fail on the very first attempt, succeed afterwards
+            if ATTEMPTS.fetch_add(1, Ordering::Relaxed) == 0 {
+                Err("not ready")
+            } else {
+                Ok(42)
+            }
+        });
+
+        // first access fails and leaves the cell uninitialized
+        assert_eq!(FOO.with_ref_lazy(|v| *v), Err("not ready"));
+        // the next access retries the stored initializer and succeeds
+        assert_eq!(FOO.with_mut_lazy(|v| *v), Ok(42));
+        // once initialized the initializer is not run again
+        assert_eq!(FOO.with_ref_lazy(|v| *v), Ok(42));
+        assert_eq!(ATTEMPTS.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn pin_init_propagates_error_and_retries() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+        static FOO: Singleton<u32, Spin, &'static str> =
+            Singleton::pin_init(&(|slot: *mut u32| -> Result<(), &'static str> {
+                // fail on the first in-place construction, succeed afterwards
+                if ATTEMPTS.fetch_add(1, Ordering::Relaxed) == 0 {
+                    Err("not ready")
+                } else {
+                    unsafe { slot.write(7) };
+                    Ok(())
+                }
+            }));
+
+        // first access fails and leaves the cell uninitialized instead of panicking the core
+        assert_eq!(FOO.try_with_pin_ref(|p| *p), Err("not ready"));
+        // the next access retries the in-place initializer and succeeds
+        assert_eq!(FOO.try_with_pin_mut(|p| *p), Ok(7));
+    }
 }
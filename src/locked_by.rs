@@ -0,0 +1,120 @@
+/***********************************************************************************************************************
+ * Copyright (c) 2019 by the authors
+ *
+ * Author: André Borrmann
+ * License: MIT / Apache License 2.0
+ **********************************************************************************************************************/
+
+//! # Sharing one lock across several data fields
+//!
+//! To reduce the number of locks a driver needs, a [LockedBy] associates an auxiliary datum with the write lock of an
+//! existing [Singleton](crate::Singleton). Accessing the datum requires presenting the live `&mut T` (or `&T`) handed
+//! out inside the owning singleton's [with_mut](crate::Singleton::with_mut) closure, which proves the lock is held.
+//! This lets a large peripheral state be split into logically separate pieces that all share the singleton's single
+//! cross-core lock without a separate lock per field.
+
+use crate::{Relax, Singleton, Spin};
+use core::cell::UnsafeCell;
+use core::convert::Infallible;
+use core::mem::size_of;
+
+/// An auxiliary datum `U` whose access is protected by the write lock of the owning [Singleton]. Construct it with the
+/// owning singleton and access its contents by passing the guard reference obtained inside
+/// [with_mut](Singleton::with_mut) / [with_ref](Singleton::with_ref).
+pub struct LockedBy<T: 'static, U, S: Relax = Spin, E: 'static = Infallible> {
+    /// the singleton whose write lock protects `data`
+    owner: *const Singleton<T, S, E>,
+    /// the protected auxiliary datum
+    data: UnsafeCell<U>,
+}
+
+// accessing `data` always requires the owning singleton's write lock to be held, hence sharing across cores is safe
+unsafe impl<T, U: Send, S: Relax, E> Send for LockedBy<T, U, S, E> {}
+unsafe impl<T, U: Send, S: Relax, E> Sync for LockedBy<T, U, S, E> {}
+
+impl<T: 'static, U, S: Relax, E: 'static> LockedBy<T, U, S, E> {
+    /// Associate the auxiliary datum `data` with the write lock of `owner`. Both are typically kept in `'static`
+    /// variables.
+    /// # Example
+    /// ```no_run
+    /// # use ruspiro_singleton::*;
+    /// static PERIPHERAL: Singleton<u32> = Singleton::new(0);
+    /// static STATE: LockedBy<u32, u32> = LockedBy::new(&PERIPHERAL, 0);
+    /// # fn main() {}
+    /// ```
+    pub const fn new(owner: &'static Singleton<T, S, E>, data: U) -> Self {
+        Self {
+            owner,
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// Mutably access the datum, proving the owning singleton's write lock is held by passing the `&mut T` guard from
+    /// its [with_mut](Singleton::with_mut) closure. Panics if the guard does not belong to the owning singleton.
+    /// # Example
+    /// ```no_run
+    /// # use ruspiro_singleton::*;
+    /// static PERIPHERAL: Singleton<u32> = Singleton::new(0);
+    /// static STATE: LockedBy<u32, u32> = LockedBy::new(&PERIPHERAL, 0);
+    /// # fn main() {
+    /// PERIPHERAL.with_mut(|p| {
+    ///     let state = STATE.access(p);
+    ///     *state += 1;
+    /// });
+    /// # }
+    /// ```
+    pub fn access<'a>(&'a self, guard: &'a mut T) -> &'a mut U {
+        assert!(
+            self.owns(guard),
+            "LockedBy accessed with a guard from a different Singleton"
+        );
+        // the caller holds the owning singleton's write lock, so exclusive access to `data` is guaranteed
+        unsafe { &mut *self.data.get() }
+    }
+
+    /// Immutably access the datum, proving the owning singleton's lock is held by passing the `&T` guard from its
+    /// [with_ref](Singleton::with_ref) closure. Panics if the guard does not belong to the owning singleton.
+    pub fn access_ref<'a>(&'a self, guard: &'a T) -> &'a U {
+        assert!(
+            self.owns(guard),
+            "LockedBy accessed with a guard from a different Singleton"
+        );
+        // the caller holds a read lock on the owning singleton, so shared access to `data` is guaranteed
+        unsafe { &*self.data.get() }
+    }
+
+    /// Pointer-identity check: the guard reference must point inside the owning singleton's storage, which is only the
+    /// case when it was obtained while holding that singleton's lock.
+    fn owns(&self, guard: *const T) -> bool {
+        let base = self.owner as *const u8;
+        let slot = guard as *const u8;
+        // SAFETY: forming the one-past-the-end pointer of the owning singleton's storage for a bounds comparison
+        let end = unsafe { base.add(size_of::<Singleton<T, S, E>>()) };
+        slot >= base && slot < end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn access_with_matching_guard_succeeds() {
+        static PERIPHERAL: Singleton<u32> = Singleton::new(0);
+        static STATE: LockedBy<u32, u32> = LockedBy::new(&PERIPHERAL, 5);
+        let seen = PERIPHERAL.with_mut(|p| *STATE.access(p));
+        assert_eq!(seen, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "different Singleton")]
+    fn access_with_foreign_guard_panics() {
+        static OWNER: Singleton<u32> = Singleton::new(0);
+        static OTHER: Singleton<u32> = Singleton::new(0);
+        static STATE: LockedBy<u32, u32> = LockedBy::new(&OWNER, 0);
+        // the guard handed out by `OTHER` does not belong to `STATE`'s owning singleton, so the identity check fails
+        OTHER.with_mut(|g| {
+            let _ = STATE.access(g);
+        });
+    }
+}
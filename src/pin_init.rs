@@ -0,0 +1,40 @@
+/***********************************************************************************************************************
+ * Copyright (c) 2019 by the authors
+ *
+ * Author: André Borrmann
+ * License: MIT / Apache License 2.0
+ **********************************************************************************************************************/
+
+//! # In-place pinned initialization
+//!
+//! Some peripheral types are self-referential or have to keep a stable address (DMA descriptors, register mirrors) and
+//! therefore must never be moved. Such a value cannot be built on the stack and moved into the `Singleton`; it has to
+//! be constructed directly at its final static address. Borrowing the idea from the Rust-for-Linux `pin-init` API a
+//! [PinInit] is an initializer that writes a `T` directly into a caller provided slot that will never move afterwards.
+
+use core::convert::Infallible;
+
+/// An in-place initializer for a value of type `T`. Implementors construct the value directly into the slot handed to
+/// [pinned_init](PinInit::pinned_init) instead of returning it by value, which is required for address sensitive types
+/// that must not be moved after construction. `E` is the error type reported when construction fails and defaults to
+/// [Infallible] for initializers that cannot fail.
+pub unsafe trait PinInit<T: ?Sized, E = Infallible> {
+    /// Initialize the value in place at `slot`. On entry `slot` points at allocated but uninitialized storage that is
+    /// guaranteed to stay at this address for the lifetime of the value. On `Ok(())` the slot is left holding a valid
+    /// `T`; on `Err(e)` the slot is left uninitialized and the error is propagated.
+    ///
+    /// # Safety
+    /// `slot` must be valid for writes and properly aligned for `T`. The caller guarantees the value is never moved
+    /// afterwards.
+    unsafe fn pinned_init(&self, slot: *mut T) -> Result<(), E>;
+}
+
+// any closure that writes into the slot is a valid in-place initializer
+unsafe impl<T, E, F> PinInit<T, E> for F
+where
+    F: Fn(*mut T) -> Result<(), E>,
+{
+    unsafe fn pinned_init(&self, slot: *mut T) -> Result<(), E> {
+        self(slot)
+    }
+}
@@ -0,0 +1,63 @@
+/***********************************************************************************************************************
+ * Copyright (c) 2019 by the authors
+ *
+ * Author: André Borrmann
+ * License: MIT / Apache License 2.0
+ **********************************************************************************************************************/
+
+//! # Relax strategies for contended spinning
+//!
+//! While a core waits for a contended lock (either the lazy initialization spinlock or the inner write lock) it has to
+//! relax the CPU in some way. The default [Spin] strategy just performs a plain busy spin which is always safe - also
+//! on the host when running the unit tests. On the Raspberry Pi cores a more power friendly approach is available: the
+//! [WaitForEvent] strategy parks the core with a `wfe` instruction until another core signals progress with a `sev`
+//! when it releases the lock. The strategy is selected as a generic type parameter on the [Singleton](crate::Singleton)
+//! defaulting to [Spin] so non-ARM and host builds keep working unchanged.
+
+/// A strategy describing how a core behaves while it spins on a contended lock.
+pub trait Relax {
+    /// Called every time an attempt to aquire a contended lock failed. Implementations may park the core or simply
+    /// hint the CPU that it is spinning.
+    fn relax();
+
+    /// Called on a lock release that could satisfy a waiting core so that parked cores get a chance to re-check the
+    /// lock state. The default implementation does nothing which is correct for strategies that never park.
+    fn notify() {}
+}
+
+/// The default relax strategy performing a plain busy spin. This is always safe and is the only strategy used on the
+/// host while running the unit tests.
+pub struct Spin;
+
+impl Relax for Spin {
+    fn relax() {
+        // give the CPU a hint that we are inside a spin loop so it may lower power usage / yield a hyperthread
+        core::hint::spin_loop();
+    }
+}
+
+/// The event driven relax strategy for the AArch64 Raspberry Pi cores. A failed aquisition parks the core with `wfe`
+/// until another core emits a `sev` on release. A spurious wake simply re-checks the lock state and parks again.
+pub struct WaitForEvent;
+
+#[cfg(target_arch = "aarch64")]
+impl Relax for WaitForEvent {
+    fn relax() {
+        // park this core until any core in the cluster sends an event
+        unsafe { core::arch::asm!("wfe", options(nomem, nostack)) };
+    }
+
+    fn notify() {
+        // wake all cores currently parked in a `wfe`
+        unsafe { core::arch::asm!("sev", options(nomem, nostack)) };
+    }
+}
+
+// on any non-AArch64 target (e.g. the host running the tests) there is no event mechanism available so the strategy
+// degrades to a plain busy spin to keep those builds working
+#[cfg(not(target_arch = "aarch64"))]
+impl Relax for WaitForEvent {
+    fn relax() {
+        Spin::relax();
+    }
+}